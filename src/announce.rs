@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use color_eyre::eyre::Result;
+use irc::client::prelude::Sender;
+
+/// Announces a now-playing string to wherever this implementation projects
+/// to — an IRC channel, an XMPP MUC, and so on. `proc_notifier` fans each new
+/// `StreamTitle` out to every configured `Announcer` instead of assuming IRC.
+#[async_trait]
+pub trait Announcer: Send + Sync {
+    async fn announce(&self, text: &str) -> Result<()>;
+}
+
+/// Announces to one or more IRC channels via `sender.send_privmsg`.
+pub struct IrcAnnouncer {
+    sender: Sender,
+    channels: Vec<String>,
+}
+
+impl IrcAnnouncer {
+    pub fn new(sender: Sender, channels: Vec<String>) -> Self {
+        Self { sender, channels }
+    }
+}
+
+#[async_trait]
+impl Announcer for IrcAnnouncer {
+    async fn announce(&self, text: &str) -> Result<()> {
+        for channel in &self.channels {
+            self.sender.send_privmsg(channel, text)?;
+        }
+        Ok(())
+    }
+}
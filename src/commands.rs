@@ -0,0 +1,189 @@
+use std::collections::{HashMap, VecDeque};
+
+use tokio::sync::watch;
+
+use crate::track::TrackEntry;
+
+/// Maps a channel name to the shared metadata of the stream announced there.
+pub type StreamRegistry = HashMap<String, SharedMeta>;
+
+/// How many recent tracks `!history` and the XSPF export keep around.
+pub const HISTORY_LEN: usize = 20;
+
+/// Latest decoded ICY metadata plus a rolling window of recently played
+/// tracks, kept up to date by `proc_notifier` and read by command handlers,
+/// the MPRIS publisher, and the XSPF export.
+#[derive(Clone, Default)]
+pub struct MetaState {
+    pub current: HashMap<String, String>,
+    pub history: VecDeque<TrackEntry>,
+}
+
+/// The read side of a stream's metadata channel; cheaply `Clone`, and
+/// `changed()` lets subscribers (such as the MPRIS publisher) wait for updates instead
+/// of polling.
+pub type SharedMeta = watch::Receiver<MetaState>;
+
+impl MetaState {
+    pub fn record_title(&mut self, raw_title: &str) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(TrackEntry::new(raw_title));
+    }
+}
+
+/// Creates a fresh metadata channel for one stream: a sender for
+/// `proc_notifier` to publish updates on, and a receiver to hand out to
+/// command handlers and other subscribers.
+pub fn meta_channel() -> (watch::Sender<MetaState>, SharedMeta) {
+    watch::channel(MetaState::default())
+}
+
+/// Dispatches `!`-prefixed commands typed in channel against the shared
+/// stream metadata.
+pub struct CommandRouter {
+    prefix: char,
+}
+
+impl CommandRouter {
+    pub fn new(prefix: char) -> Self {
+        Self { prefix }
+    }
+
+    /// Returns the reply text for `msg` sent in `channel`, or `None` if it
+    /// isn't a recognized command (or `channel` has no stream announced to it).
+    pub fn handle(&self, msg: &str, channel: &str, streams: &StreamRegistry) -> Option<String> {
+        let rest = msg.strip_prefix(self.prefix)?;
+        let mut parts = rest.split_whitespace();
+        let cmd = parts.next()?;
+
+        if cmd == "help" {
+            return Some(format!(
+                "commands: {p}np, {p}history, {p}help",
+                p = self.prefix
+            ));
+        }
+
+        let meta = streams.get(channel)?;
+        match cmd {
+            "np" => Some(self.now_playing(meta)),
+            "history" => Some(self.history(meta)),
+            _ => None,
+        }
+    }
+
+    fn now_playing(&self, meta: &SharedMeta) -> String {
+        let meta = meta.borrow();
+        match meta.current.get("StreamTitle") {
+            Some(title) => format!("now playing: {}", title),
+            None => "nothing is playing right now".to_owned(),
+        }
+    }
+
+    fn history(&self, meta: &SharedMeta) -> String {
+        let meta = meta.borrow();
+        if meta.history.is_empty() {
+            return "no tracks played yet".to_owned();
+        }
+        meta.history
+            .iter()
+            .rev()
+            .map(|entry| match &entry.artist {
+                Some(artist) => format!("{} - {}", artist, entry.title),
+                None => entry.title.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry_with(channel: &str) -> (StreamRegistry, watch::Sender<MetaState>) {
+        let (tx, rx) = meta_channel();
+        let mut streams = StreamRegistry::new();
+        streams.insert(channel.to_owned(), rx);
+        (streams, tx)
+    }
+
+    #[test]
+    fn help_works_regardless_of_channel() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#other");
+        assert_eq!(
+            router.handle("!help", "#unconfigured", &streams),
+            Some("commands: !np, !history, !help".to_owned())
+        );
+    }
+
+    #[test]
+    fn ignores_messages_without_the_prefix() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#chan");
+        assert_eq!(router.handle("np", "#chan", &streams), None);
+    }
+
+    #[test]
+    fn unknown_command_in_configured_channel_is_none() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#chan");
+        assert_eq!(router.handle("!frobnicate", "#chan", &streams), None);
+    }
+
+    #[test]
+    fn np_in_unconfigured_channel_is_none() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#chan");
+        assert_eq!(router.handle("!np", "#other", &streams), None);
+    }
+
+    #[test]
+    fn np_before_any_metadata() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#chan");
+        assert_eq!(
+            router.handle("!np", "#chan", &streams),
+            Some("nothing is playing right now".to_owned())
+        );
+    }
+
+    #[test]
+    fn np_reports_current_title() {
+        let router = CommandRouter::new('!');
+        let (streams, tx) = registry_with("#chan");
+        tx.send_modify(|meta| {
+            meta.current.insert("StreamTitle".to_owned(), "Artist - Title".to_owned());
+        });
+        assert_eq!(
+            router.handle("!np", "#chan", &streams),
+            Some("now playing: Artist - Title".to_owned())
+        );
+    }
+
+    #[test]
+    fn history_before_any_tracks() {
+        let router = CommandRouter::new('!');
+        let (streams, _tx) = registry_with("#chan");
+        assert_eq!(
+            router.handle("!history", "#chan", &streams),
+            Some("no tracks played yet".to_owned())
+        );
+    }
+
+    #[test]
+    fn history_lists_newest_first() {
+        let router = CommandRouter::new('!');
+        let (streams, tx) = registry_with("#chan");
+        tx.send_modify(|meta| {
+            meta.record_title("First Song");
+            meta.record_title("Second Song");
+        });
+        assert_eq!(
+            router.handle("!history", "#chan", &streams),
+            Some("Second Song | First Song".to_owned())
+        );
+    }
+}
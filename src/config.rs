@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use serde::Deserialize;
+
+/// Top-level configuration: one IRC connection, fanning out to one or more
+/// Icecast streams, each announced to its own set of channels.
+#[derive(Debug, Deserialize)]
+pub struct HecateConfig {
+    pub irc: IrcConfig,
+    #[serde(rename = "stream")]
+    pub streams: Vec<StreamConfig>,
+    #[serde(default)]
+    pub mpris: MprisConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub xmpp: Option<XmppConfig>,
+}
+
+/// Mirrors now-playing announcements to these MUCs over XMPP, alongside IRC.
+#[derive(Debug, Deserialize)]
+pub struct XmppConfig {
+    pub jid: String,
+    pub password: String,
+    pub rooms: Vec<String>,
+}
+
+/// Whether to publish now-playing metadata over MPRIS2 on the session D-Bus.
+#[derive(Debug, Default, Deserialize)]
+pub struct MprisConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Whether to serve each stream's play history as XSPF, and Prometheus
+/// metrics, over HTTP.
+#[derive(Debug, Deserialize)]
+pub struct HttpConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_http_bind")]
+    pub bind: String,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_http_bind(),
+        }
+    }
+}
+
+fn default_http_bind() -> String {
+    "127.0.0.1:8420".to_owned()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IrcConfig {
+    pub server: String,
+    pub port: u16,
+    #[serde(default)]
+    pub use_tls: bool,
+    pub nickname: String,
+}
+
+/// One Icecast mount and the channels its now-playing announcements go to.
+#[derive(Debug, Deserialize)]
+pub struct StreamConfig {
+    pub url: String,
+    pub channels: Vec<String>,
+}
+
+impl HecateConfig {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let raw = fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    /// All channels referenced by any configured stream, for joining on connect.
+    pub fn all_channels(&self) -> Vec<String> {
+        let mut channels: Vec<String> = self
+            .streams
+            .iter()
+            .flat_map(|s| s.channels.iter().cloned())
+            .collect();
+        channels.sort();
+        channels.dedup();
+        channels
+    }
+}
@@ -0,0 +1,16 @@
+use std::net::SocketAddr;
+
+use color_eyre::eyre::Result;
+use tracing::info;
+
+use crate::metrics::MetricsState;
+use crate::xspf::XspfState;
+
+/// Serves XSPF history and Prometheus metrics on one embedded HTTP server.
+pub async fn serve(addr: SocketAddr, xspf_state: XspfState, metrics_state: MetricsState) -> Result<()> {
+    let app = crate::xspf::router(xspf_state).merge(crate::metrics::router(metrics_state));
+
+    info!(%addr, "serving HTTP (XSPF history, metrics)");
+    axum::Server::bind(&addr).serve(app.into_make_service()).await?;
+    Ok(())
+}
@@ -1,10 +1,29 @@
 use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 
 use futures_util::StreamExt;
 use irc::client::prelude::*;
 use color_eyre::eyre::{eyre, Result};
+use rand::Rng;
 use tokio::task;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
+
+mod announce;
+mod commands;
+mod config;
+mod http;
+mod metrics;
+mod mpris;
+mod track;
+mod xmpp_announce;
+mod xspf;
+
+use announce::{Announcer, IrcAnnouncer};
+use commands::{meta_channel, CommandRouter, MetaState, StreamRegistry};
+use config::HecateConfig;
+use metrics::SharedMetrics;
+use tokio::sync::watch;
 
 enum State {
     SkipAudio(usize),
@@ -36,14 +55,90 @@ fn decode_meta(meta: &String) -> HashMap<String, String> {
         .collect()
 }
 
-async fn proc_notifier(sender: Sender) -> Result<()> {
+/// Base delay before the first reconnect attempt.
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+/// Cap on the reconnect delay, regardless of how many attempts have failed.
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// A connection has to survive this long before we consider it stable and
+/// reset the backoff back to `BACKOFF_BASE`.
+const STABLE_CONNECTION: Duration = Duration::from_secs(30);
+
+/// Applies +/-50% jitter to `delay` to avoid thundering-herd reconnects.
+fn jittered(delay: Duration) -> Duration {
+    let factor = rand::thread_rng().gen_range(0.5..1.5);
+    Duration::from_secs_f64(delay.as_secs_f64() * factor)
+}
+
+/// Decides the next reconnect delay and attempt count for `proc_notifier`:
+/// resets to `BACKOFF_BASE` after a stable connection, otherwise doubles the
+/// previous delay (capped at `BACKOFF_MAX`).
+fn next_backoff(delay: Duration, attempt: u32, stable: bool) -> (Duration, u32) {
+    if stable {
+        (BACKOFF_BASE, 0)
+    } else {
+        ((delay * 2).min(BACKOFF_MAX), attempt + 1)
+    }
+}
+
+/// Supervises `connect_and_notify`, reconnecting with exponential backoff
+/// whenever the stream drops, instead of letting the task die silently.
+async fn proc_notifier(
+    meta_state: watch::Sender<MetaState>,
+    url: String,
+    announcers: Vec<Arc<dyn Announcer>>,
+    metrics: SharedMetrics,
+) {
+    let mut delay = BACKOFF_BASE;
+    let mut attempt: u32 = 0;
+    let mut first_connect = true;
+
+    loop {
+        if !first_connect {
+            metrics.mark_reconnect();
+        }
+        first_connect = false;
+
+        if let Err(err) = connect_and_notify(&meta_state, &url, &announcers, &metrics).await {
+            warn!(error = %err, url = %url, "stream connection failed");
+        } else {
+            warn!(url = %url, "stream closed");
+        }
+
+        // Only the time actually spent connected (from `mark_connected` in
+        // `connect_and_notify`) counts toward stability — not time spent on
+        // a slow-to-fail attempt that never got a working connection.
+        let stable = metrics
+            .connected_duration()
+            .is_some_and(|d| d >= STABLE_CONNECTION);
+        metrics.mark_disconnected();
+
+        if stable {
+            (delay, attempt) = next_backoff(delay, attempt, true);
+        }
+
+        let wait = jittered(delay);
+        warn!(attempt, delay = ?wait, "reconnecting to stream");
+        tokio::time::sleep(wait).await;
+
+        (delay, attempt) = next_backoff(delay, attempt, false);
+    }
+}
+
+async fn connect_and_notify(
+    meta_state: &watch::Sender<MetaState>,
+    url: &str,
+    announcers: &[Arc<dyn Announcer>],
+    metrics: &SharedMetrics,
+) -> Result<()> {
     let cl = reqwest::Client::new();
-    let res = cl.get("http://sleepy.zone:8000/blissomradio")
+    let res = cl.get(url)
         .header("Icy-MetaData", "1")
         .send()
         .await?;
     let metaint: usize = res.headers().get("icy-metaint").ok_or_else(|| eyre!("no icy-metaint resp header"))?.to_str()?.parse()?;
     info!(metaint = metaint, "connected to stream");
+    metrics.set_metaint(metaint as u64);
+    metrics.mark_connected();
 
     let mut stream = res.bytes_stream();
 
@@ -51,6 +146,7 @@ async fn proc_notifier(sender: Sender) -> Result<()> {
     let mut data: VecDeque<u8> = VecDeque::with_capacity(metaint);
     while let Some(chunk) = stream.next().await.transpose()? {
         info!("chunk");
+        metrics.add_bytes(chunk.len() as u64);
         data.extend(chunk);
         while data.len() >= state.bytes_to_consume() {
             state = match state {
@@ -67,10 +163,33 @@ async fn proc_notifier(sender: Sender) -> Result<()> {
                     let meta: String = String::from_utf8(data.drain(0..n).collect())?;
 
                     if meta.len() > 0 {
-                        let meta = decode_meta(&meta);
-                        info!(meta=?meta, "metadata");
-                        if let Some(title) = meta.get("StreamTitle") {
-                            sender.send_privmsg("#sleepyfm", format!("now playing: {}", title))?;
+                        let decoded = decode_meta(&meta);
+                        info!(meta=?decoded, "metadata");
+                        if let Some(title) = decoded.get("StreamTitle") {
+                            // Icecast resends the same StreamTitle on every
+                            // metaint tick for as long as a track keeps
+                            // playing, not just on track change.
+                            let changed = meta_state.borrow().current.get("StreamTitle") != Some(title);
+
+                            if changed {
+                                let text = format!("now playing: {}", title);
+                                for announcer in announcers {
+                                    if let Err(err) = announcer.announce(&text).await {
+                                        warn!(error = %err, "announce failed");
+                                    }
+                                }
+
+                                metrics.mark_track();
+
+                                // Only push a watch update on an actual track
+                                // change, so `run_mpris`'s subscribers (which
+                                // wake on `changed()`) don't get a no-op
+                                // metadata/playback-status signal every tick.
+                                let mut next = meta_state.borrow().clone();
+                                next.record_title(title);
+                                next.current = decoded;
+                                meta_state.send(next)?;
+                            }
                         }
                     }
 
@@ -88,27 +207,92 @@ async fn main() -> Result<()> {
     color_eyre::install()?;
     tracing_subscriber::fmt::init();
 
-    let config = Config {
-        nickname: Some("Hecate".to_owned()),
-        server: Some("irc.sleepy.zone".to_owned()),
-        port: Some(6667),
-        use_tls: Some(false),
-        channels: vec!["#sleepyfm".to_owned()],
+    let config_path = std::env::args().nth(1).unwrap_or_else(|| "hecate.toml".to_owned());
+    let hecate_config = HecateConfig::load(&config_path)?;
+
+    let irc_config = Config {
+        nickname: Some(hecate_config.irc.nickname.clone()),
+        server: Some(hecate_config.irc.server.clone()),
+        port: Some(hecate_config.irc.port),
+        use_tls: Some(hecate_config.irc.use_tls),
+        channels: hecate_config.all_channels(),
         ..Config::default()
     };
 
-    let mut client = Client::from_config(config).await?;
+    let mut client = Client::from_config(irc_config).await?;
     client.identify()?;
 
     let mut stream = client.stream()?;
     let sender = client.sender();
 
-    let _jh = task::spawn(proc_notifier(sender.clone()));
+    let router = CommandRouter::new('!');
+    let mut streams = StreamRegistry::new();
+    let mut xspf_streams = HashMap::new();
+    let mut metrics_streams = HashMap::new();
+
+    let xmpp_announcer: Option<Arc<xmpp_announce::XmppAnnouncer>> = match &hecate_config.xmpp {
+        Some(xmpp_config) => Some(Arc::new(
+            xmpp_announce::XmppAnnouncer::connect(
+                &xmpp_config.jid,
+                &xmpp_config.password,
+                xmpp_config.rooms.clone(),
+            )
+            .await?,
+        )),
+        None => None,
+    };
+
+    for (i, stream_config) in hecate_config.streams.iter().enumerate() {
+        let (meta_tx, meta_rx) = meta_channel();
+        for channel in &stream_config.channels {
+            streams.insert(channel.clone(), meta_rx.clone());
+        }
+        xspf_streams.insert(i.to_string(), meta_rx.clone());
+
+        let metrics: SharedMetrics = SharedMetrics::default();
+        metrics_streams.insert(i.to_string(), metrics.clone());
+
+        let mut announcers: Vec<Arc<dyn Announcer>> = vec![Arc::new(IrcAnnouncer::new(
+            sender.clone(),
+            stream_config.channels.clone(),
+        ))];
+        if let Some(xmpp_announcer) = &xmpp_announcer {
+            announcers.push(xmpp_announcer.clone());
+        }
+
+        let _jh = task::spawn(proc_notifier(meta_tx, stream_config.url.clone(), announcers, metrics));
+
+        if hecate_config.mpris.enabled {
+            let stream_id = i.to_string();
+            task::spawn(async move {
+                if let Err(err) = mpris::run_mpris(meta_rx, stream_id.clone()).await {
+                    warn!(error = %err, stream = %stream_id, "MPRIS publisher exited");
+                }
+            });
+        }
+    }
+
+    if hecate_config.http.enabled {
+        let addr = hecate_config.http.bind.parse()?;
+        task::spawn(async move {
+            let result = http::serve(
+                addr,
+                xspf::XspfState { streams: xspf_streams },
+                metrics::MetricsState { streams: metrics_streams },
+            )
+            .await;
+            if let Err(err) = result {
+                warn!(error = %err, %addr, "HTTP server exited");
+            }
+        });
+    }
 
     while let Some(message) = stream.next().await.transpose()? {
         match message.command {
             Command::PRIVMSG(ref tgt, ref msg) => {
-                if msg.contains(client.current_nickname()) {
+                if let Some(reply) = router.handle(msg, tgt, &streams) {
+                    sender.send_privmsg(tgt, reply)?;
+                } else if msg.contains(client.current_nickname()) {
                     sender.send_privmsg(tgt, "hi!")?;
                 }
             },
@@ -118,3 +302,58 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_plus_minus_50_percent() {
+        let delay = Duration::from_secs(10);
+        for _ in 0..1000 {
+            let jittered = jittered(delay);
+            assert!(jittered >= Duration::from_secs_f64(5.0));
+            assert!(jittered <= Duration::from_secs_f64(15.0));
+        }
+    }
+
+    #[test]
+    fn decode_meta_parses_key_value_pairs() {
+        let meta = "StreamTitle='Artist - Title';StreamUrl='http://example.com';".to_owned();
+        let decoded = decode_meta(&meta);
+        assert_eq!(decoded.get("StreamTitle").map(String::as_str), Some("Artist - Title"));
+        assert_eq!(decoded.get("StreamUrl").map(String::as_str), Some("http://example.com"));
+    }
+
+    #[test]
+    fn decode_meta_ignores_padding_and_empty_segments() {
+        let meta = "StreamTitle='Only One';\0\0\0".to_owned();
+        let decoded = decode_meta(&meta);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded.get("StreamTitle").map(String::as_str), Some("Only One"));
+    }
+
+    #[test]
+    fn backoff_doubles_on_repeated_failure() {
+        let (delay, attempt) = next_backoff(BACKOFF_BASE, 0, false);
+        assert_eq!(delay, Duration::from_secs(2));
+        assert_eq!(attempt, 1);
+
+        let (delay, attempt) = next_backoff(delay, attempt, false);
+        assert_eq!(delay, Duration::from_secs(4));
+        assert_eq!(attempt, 2);
+    }
+
+    #[test]
+    fn backoff_caps_at_backoff_max() {
+        let (delay, _) = next_backoff(BACKOFF_MAX, 0, false);
+        assert_eq!(delay, BACKOFF_MAX);
+    }
+
+    #[test]
+    fn backoff_resets_after_a_stable_connection() {
+        let (delay, attempt) = next_backoff(BACKOFF_MAX, 5, true);
+        assert_eq!(delay, BACKOFF_BASE);
+        assert_eq!(attempt, 0);
+    }
+}
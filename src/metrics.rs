@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+/// Operational counters and gauges for one configured stream, updated by
+/// `proc_notifier`'s state machine and scraped by `/metrics`.
+#[derive(Default)]
+pub struct StreamMetrics {
+    pub tracks_announced: AtomicU64,
+    pub reconnects: AtomicU64,
+    pub bytes_consumed: AtomicU64,
+    pub metaint: AtomicU64,
+    connected_at: Mutex<Option<Instant>>,
+    last_track_at: Mutex<Option<Instant>>,
+}
+
+pub type SharedMetrics = Arc<StreamMetrics>;
+
+impl StreamMetrics {
+    pub fn mark_connected(&self) {
+        *self.connected_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// How long the current connection (if any) has been up. `proc_notifier`
+    /// uses this to decide whether to reset the reconnect backoff.
+    pub fn connected_duration(&self) -> Option<Duration> {
+        self.connected_at.lock().unwrap().map(|t| t.elapsed())
+    }
+
+    /// Clears the connected gauge once a stream drops, so
+    /// `hecate_stream_seconds_connected` doesn't keep climbing through a
+    /// disconnected/retrying period as if the stream were still up.
+    pub fn mark_disconnected(&self) {
+        *self.connected_at.lock().unwrap() = None;
+    }
+
+    pub fn mark_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn mark_track(&self) {
+        self.tracks_announced.fetch_add(1, Ordering::Relaxed);
+        *self.last_track_at.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub fn add_bytes(&self, n: u64) {
+        self.bytes_consumed.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn set_metaint(&self, metaint: u64) {
+        self.metaint.store(metaint, Ordering::Relaxed);
+    }
+
+    fn seconds_connected(&self) -> f64 {
+        self.connected_at
+            .lock()
+            .unwrap()
+            .map_or(0.0, |t| t.elapsed().as_secs_f64())
+    }
+
+    fn seconds_since_last_track(&self) -> Option<f64> {
+        self.last_track_at.lock().unwrap().map(|t| t.elapsed().as_secs_f64())
+    }
+}
+
+/// Maps a stream id (its index in the config) to that stream's metrics.
+#[derive(Clone, Default)]
+pub struct MetricsState {
+    pub streams: HashMap<String, SharedMetrics>,
+}
+
+pub fn router(state: MetricsState) -> Router {
+    Router::new().route("/metrics", get(metrics_text)).with_state(state)
+}
+
+async fn metrics_text(State(state): State<MetricsState>) -> impl IntoResponse {
+    let mut out = String::new();
+    write_gauge_header(&mut out, "hecate_tracks_announced_total", "counter", "Tracks announced to the configured announcers.");
+    for (id, m) in &state.streams {
+        writeln!(out, "hecate_tracks_announced_total{{stream=\"{id}\"}} {}", m.tracks_announced.load(Ordering::Relaxed)).ok();
+    }
+
+    write_gauge_header(&mut out, "hecate_stream_reconnects_total", "counter", "Reconnect attempts made after the stream connection dropped or failed.");
+    for (id, m) in &state.streams {
+        writeln!(out, "hecate_stream_reconnects_total{{stream=\"{id}\"}} {}", m.reconnects.load(Ordering::Relaxed)).ok();
+    }
+
+    write_gauge_header(&mut out, "hecate_stream_bytes_consumed_total", "counter", "Bytes read from the Icecast stream.");
+    for (id, m) in &state.streams {
+        writeln!(out, "hecate_stream_bytes_consumed_total{{stream=\"{id}\"}} {}", m.bytes_consumed.load(Ordering::Relaxed)).ok();
+    }
+
+    write_gauge_header(&mut out, "hecate_stream_metaint", "gauge", "Current ICY metaint, in bytes.");
+    for (id, m) in &state.streams {
+        writeln!(out, "hecate_stream_metaint{{stream=\"{id}\"}} {}", m.metaint.load(Ordering::Relaxed)).ok();
+    }
+
+    write_gauge_header(&mut out, "hecate_stream_seconds_connected", "gauge", "Seconds since the current connection was established.");
+    for (id, m) in &state.streams {
+        writeln!(out, "hecate_stream_seconds_connected{{stream=\"{id}\"}} {}", m.seconds_connected()).ok();
+    }
+
+    write_gauge_header(&mut out, "hecate_stream_seconds_since_last_track", "gauge", "Seconds since metadata last changed.");
+    for (id, m) in &state.streams {
+        if let Some(secs) = m.seconds_since_last_track() {
+            writeln!(out, "hecate_stream_seconds_since_last_track{{stream=\"{id}\"}} {}", secs).ok();
+        }
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+fn write_gauge_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").ok();
+    writeln!(out, "# TYPE {name} {kind}").ok();
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::Result;
+use tracing::info;
+use zbus::{dbus_interface, zvariant::Value, ConnectionBuilder};
+
+use crate::commands::SharedMeta;
+use crate::track::split_artist_title;
+
+struct Player {
+    artist: String,
+    title: String,
+}
+
+#[dbus_interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    #[dbus_interface(property)]
+    fn playback_status(&self) -> String {
+        "Playing".to_owned()
+    }
+
+    #[dbus_interface(property)]
+    fn metadata(&self) -> HashMap<String, Value> {
+        let mut meta = HashMap::new();
+        meta.insert("xesam:artist".to_owned(), vec![self.artist.clone()].into());
+        meta.insert("xesam:title".to_owned(), self.title.clone().into());
+        meta
+    }
+}
+
+/// Publishes now-playing metadata for one stream over MPRIS2, so desktop
+/// status bars and scrobblers can see Hecate as if it were a local player.
+/// `bus_suffix` keeps multiple configured streams from fighting over the
+/// same well-known bus name.
+pub async fn run_mpris(mut meta: SharedMeta, bus_suffix: String) -> Result<()> {
+    let player = Player {
+        artist: String::new(),
+        title: String::new(),
+    };
+
+    let connection = ConnectionBuilder::session()?
+        .name(format!("org.mpris.MediaPlayer2.hecate.{}", bus_suffix))?
+        .serve_at("/org/mpris/MediaPlayer2", player)?
+        .build()
+        .await?;
+
+    info!(bus_suffix = %bus_suffix, "publishing MPRIS2 player");
+
+    loop {
+        meta.changed().await?;
+        let Some(raw_title) = meta.borrow().current.get("StreamTitle").cloned() else {
+            continue;
+        };
+        let (artist, title) = split_artist_title(&raw_title);
+        let artist = artist.unwrap_or_default();
+
+        let iface_ref = connection
+            .object_server()
+            .interface::<_, Player>("/org/mpris/MediaPlayer2")
+            .await?;
+        let mut iface = iface_ref.get_mut().await;
+        iface.artist = artist;
+        iface.title = title;
+        iface.metadata_changed(iface_ref.signal_context()).await?;
+        iface
+            .playback_status_changed(iface_ref.signal_context())
+            .await?;
+    }
+}
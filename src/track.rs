@@ -0,0 +1,73 @@
+use std::time::SystemTime;
+
+/// One played track: its `StreamTitle` split into artist/title, and when it
+/// was captured.
+#[derive(Clone)]
+pub struct TrackEntry {
+    pub artist: Option<String>,
+    pub title: String,
+    pub played_at: SystemTime,
+}
+
+impl TrackEntry {
+    pub fn new(raw_title: &str) -> Self {
+        let (artist, title) = split_artist_title(raw_title);
+        Self {
+            artist,
+            title,
+            played_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Splits a `StreamTitle` of the common `Artist - Title` convention; streams
+/// that don't follow it end up with no artist.
+pub fn split_artist_title(raw: &str) -> (Option<String>, String) {
+    match raw.split_once(" - ") {
+        Some((artist, title)) => (Some(artist.trim().to_owned()), title.trim().to_owned()),
+        None => (None, raw.trim().to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_first_separator() {
+        assert_eq!(
+            split_artist_title("Boards of Canada - Roygbiv"),
+            (Some("Boards of Canada".to_owned()), "Roygbiv".to_owned())
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_first_split() {
+        // A title that itself contains " - " shouldn't get chopped further.
+        assert_eq!(
+            split_artist_title("Boards of Canada - Roygbiv - Reprise"),
+            (Some("Boards of Canada".to_owned()), "Roygbiv - Reprise".to_owned())
+        );
+    }
+
+    #[test]
+    fn no_separator_has_no_artist() {
+        assert_eq!(
+            split_artist_title("just a title"),
+            (None, "just a title".to_owned())
+        );
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        assert_eq!(
+            split_artist_title("  Artist   -   Title  "),
+            (Some("Artist".to_owned()), "Title".to_owned())
+        );
+    }
+
+    #[test]
+    fn empty_raw_title() {
+        assert_eq!(split_artist_title(""), (None, "".to_owned()));
+    }
+}
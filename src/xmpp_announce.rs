@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use jid::BareJid;
+use tokio::sync::mpsc;
+use tokio::task;
+use tracing::{info, warn};
+use xmpp::{Agent, ClientBuilder, ClientType, Event};
+
+use crate::announce::Announcer;
+use crate::{jittered, BACKOFF_BASE, BACKOFF_MAX};
+
+/// Announces to one or more XMPP MUCs, modeled on the IRC `Announcer`.
+///
+/// The `xmpp`/`tokio-xmpp` `Agent` has to be driven by repeatedly calling
+/// `wait_for_events` for as long as the connection is open — that's what
+/// flushes outgoing stanzas, reads incoming ones, and answers keepalives.
+/// `connect` hands the agent off to a `supervise` task that owns it for the
+/// announcer's whole lifetime, pumping events, sending announced text via a
+/// channel, and reconnecting with the same kind of backoff as
+/// `proc_notifier` whenever the connection drops — so a single XMPP hiccup
+/// doesn't leave every stream's `announce()` writing into a dead channel.
+pub struct XmppAnnouncer {
+    outgoing: mpsc::UnboundedSender<(BareJid, String)>,
+    rooms: Vec<BareJid>,
+}
+
+impl XmppAnnouncer {
+    /// Connects to the XMPP server for `jid`, waits until online, joins
+    /// every room in `rooms`, then spawns the task that keeps the
+    /// connection alive (reconnecting as needed) for as long as the
+    /// returned `XmppAnnouncer` lives.
+    pub async fn connect(jid: &str, password: &str, rooms: Vec<String>) -> Result<Self> {
+        let bare_jid: BareJid = jid.parse()?;
+        let rooms: Vec<BareJid> = rooms.iter().map(|r| r.parse()).collect::<Result<_, _>>()?;
+        let password = password.to_owned();
+
+        let agent = connect_and_join(bare_jid.clone(), &password, &rooms).await?;
+
+        let (outgoing, to_send) = mpsc::unbounded_channel::<(BareJid, String)>();
+        task::spawn(supervise(bare_jid, password, rooms.clone(), agent, to_send));
+
+        Ok(Self { outgoing, rooms })
+    }
+}
+
+/// Builds a fresh `Agent`, waits for it to come online, and joins `rooms`.
+async fn connect_and_join(jid: BareJid, password: &str, rooms: &[BareJid]) -> Result<Agent> {
+    let mut agent = ClientBuilder::new(jid, password)
+        .set_client(ClientType::Bot, "hecate")
+        .set_default_nick("hecate")
+        .build();
+
+    loop {
+        match agent.wait_for_events().await {
+            Some(events) if events.iter().any(|e| matches!(e, Event::Online)) => break,
+            Some(_) => continue,
+            None => return Err(eyre!("XMPP connection closed before coming online")),
+        }
+    }
+
+    for room in rooms {
+        info!(room = %room, "joining MUC");
+        agent
+            .join_room(room.clone(), Some("hecate".to_owned()), None, None, "en")
+            .await;
+    }
+
+    Ok(agent)
+}
+
+/// Pumps `agent`'s event loop and forwards queued announce text to it until
+/// the connection drops, then reconnects with exponential backoff (mirroring
+/// `proc_notifier`) and resumes — for the announcer's entire lifetime.
+async fn supervise(
+    jid: BareJid,
+    password: String,
+    rooms: Vec<BareJid>,
+    mut agent: Agent,
+    mut to_send: mpsc::UnboundedReceiver<(BareJid, String)>,
+) {
+    let mut delay = BACKOFF_BASE;
+
+    loop {
+        loop {
+            tokio::select! {
+                events = agent.wait_for_events() => {
+                    if events.is_none() {
+                        warn!("XMPP connection closed");
+                        break;
+                    }
+                }
+                msg = to_send.recv() => {
+                    let Some((room, text)) = msg else { return };
+                    agent
+                        .send_message(jid::Jid::Bare(room), xmpp::MessageType::Groupchat, "en", &text)
+                        .await;
+                }
+            }
+        }
+
+        loop {
+            let wait = jittered(delay);
+            warn!(delay = ?wait, "reconnecting to XMPP");
+            tokio::time::sleep(wait).await;
+            delay = (delay * 2).min(BACKOFF_MAX);
+
+            match connect_and_join(jid.clone(), &password, &rooms).await {
+                Ok(new_agent) => {
+                    agent = new_agent;
+                    delay = BACKOFF_BASE;
+                    break;
+                }
+                Err(err) => warn!(error = %err, "XMPP reconnect failed"),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Announcer for XmppAnnouncer {
+    async fn announce(&self, text: &str) -> Result<()> {
+        for room in &self.rooms {
+            self.outgoing.send((room.clone(), text.to_owned()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::{HashMap, VecDeque};
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+
+use crate::commands::SharedMeta;
+use crate::track::TrackEntry;
+
+/// Maps a stream id (its index in the config) to that stream's metadata, so
+/// `GET /streams/:id/history.xspf` can find the right history.
+#[derive(Clone)]
+pub struct XspfState {
+    pub streams: HashMap<String, SharedMeta>,
+}
+
+/// Routes serving each configured stream's play history as an XSPF playlist.
+pub fn router(state: XspfState) -> Router {
+    Router::new()
+        .route("/streams/:id/history.xspf", get(history_xspf))
+        .with_state(state)
+}
+
+async fn history_xspf(Path(id): Path<String>, State(state): State<XspfState>) -> Response {
+    let Some(meta) = state.streams.get(&id) else {
+        return (StatusCode::NOT_FOUND, "unknown stream").into_response();
+    };
+
+    let history = meta.borrow().history.clone();
+    let body = render_xspf(&history);
+    ([(header::CONTENT_TYPE, "application/xspf+xml")], body).into_response()
+}
+
+fn render_xspf(history: &VecDeque<TrackEntry>) -> String {
+    let mut tracks = String::new();
+    for entry in history.iter().rev() {
+        let title = escape_xml(&entry.title);
+        let creator = entry.artist.as_deref().map(escape_xml).unwrap_or_default();
+        let annotation = humantime::format_rfc3339_seconds(entry.played_at);
+        tracks.push_str(&format!(
+            "<track><title>{title}</title><creator>{creator}</creator><annotation>{annotation}</annotation></track>"
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><playlist version="1" xmlns="http://xspf.org/ns/0/"><trackList>{tracks}</trackList></playlist>"#
+    )
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn escape_xml_replaces_all_special_characters() {
+        assert_eq!(
+            escape_xml(r#"Tom & Jerry's "Cat & Mouse" <Show>"#),
+            "Tom &amp; Jerry&apos;s &quot;Cat &amp; Mouse&quot; &lt;Show&gt;"
+        );
+    }
+
+    #[test]
+    fn escape_xml_leaves_plain_text_untouched() {
+        assert_eq!(escape_xml("Roygbiv"), "Roygbiv");
+    }
+
+    #[test]
+    fn render_xspf_of_empty_history() {
+        let history = VecDeque::new();
+        assert_eq!(
+            render_xspf(&history),
+            r#"<?xml version="1.0" encoding="UTF-8"?><playlist version="1" xmlns="http://xspf.org/ns/0/"><trackList></trackList></playlist>"#
+        );
+    }
+
+    #[test]
+    fn render_xspf_orders_newest_first_and_escapes() {
+        let played_at = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(0);
+        let mut history = VecDeque::new();
+        history.push_back(TrackEntry {
+            artist: Some("A&B".to_owned()),
+            title: "First".to_owned(),
+            played_at,
+        });
+        history.push_back(TrackEntry {
+            artist: None,
+            title: "Second".to_owned(),
+            played_at,
+        });
+
+        let xml = render_xspf(&history);
+        let first_pos = xml.find("First").unwrap();
+        let second_pos = xml.find("Second").unwrap();
+        assert!(second_pos < first_pos, "most recently played track should come first");
+        assert!(xml.contains("<creator>A&amp;B</creator>"));
+        assert!(xml.contains("<creator></creator>"));
+    }
+}